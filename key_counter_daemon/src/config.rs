@@ -0,0 +1,180 @@
+// src/config.rs
+//
+// Everything that used to be a hardcoded `const` at the top of `main.rs` —
+// the state file paths, the sound files, which keyboards to listen on, and
+// the difficulty thresholds — lives here instead, loaded from a TOML file so
+// the daemon doesn't need a recompile to run on anyone else's machine. Every
+// field has a default (the old constant's value, where there was one), so an absent or partial
+// config file still produces a working setup.
+
+use rand::Rng;
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::PathBuf;
+
+fn default_keyboards() -> Vec<String> {
+    vec!["GMMK Pro Keyboard".to_string(), "Translated".to_string()]
+}
+
+fn default_counter_file() -> String {
+    "/tmp/waybar_counter.txt".to_string()
+}
+
+fn default_workspace_state_file() -> String {
+    "/tmp/waybar_status.txt".to_string()
+}
+
+fn default_volume_file() -> String {
+    "/tmp/waybar_volume.txt".to_string()
+}
+
+fn default_sounds() -> HashMap<String, Vec<String>> {
+    let mut sounds = HashMap::new();
+    sounds.insert(
+        "increment".to_string(),
+        vec!["/home/jake/Music/Sonic-Ring.mp3".to_string()],
+    );
+    sounds.insert(
+        "special_intro".to_string(),
+        vec!["/home/jake/Music/Super-Sonic-Transform.mp3".to_string()],
+    );
+    sounds.insert(
+        "special_loop".to_string(),
+        vec!["/home/jake/Music/Super-sonic-song.mp3".to_string()],
+    );
+    sounds
+}
+
+fn default_special_mode_threshold() -> u32 {
+    50
+}
+
+fn default_backslash_trigger_count() -> u8 {
+    3
+}
+
+// The random keystroke-target range for each `GameMode`, inclusive on both
+// ends (fed straight into `gen_range`).
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct DifficultyConfig {
+    pub(crate) test_range: (u32, u32),
+    pub(crate) normal_range: (u32, u32),
+    pub(crate) hard_range: (u32, u32),
+}
+
+impl Default for DifficultyConfig {
+    fn default() -> Self {
+        Self {
+            test_range: (1, 1),
+            normal_range: (1, 100),
+            hard_range: (1, 1000),
+        }
+    }
+}
+
+impl DifficultyConfig {
+    // An inverted range from user TOML (e.g. `normal_range = [10, 1]`) would
+    // panic `gen_range` on the very next keystroke, so swap each pair into
+    // `(low, high)` order up front rather than trusting the file.
+    fn normalized(mut self) -> Self {
+        fn sorted(range: (u32, u32)) -> (u32, u32) {
+            if range.0 <= range.1 {
+                range
+            } else {
+                (range.1, range.0)
+            }
+        }
+        self.test_range = sorted(self.test_range);
+        self.normal_range = sorted(self.normal_range);
+        self.hard_range = sorted(self.hard_range);
+        self
+    }
+}
+
+#[derive(Debug, serde::Deserialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    #[serde(default = "default_keyboards")]
+    pub(crate) keyboards: Vec<String>,
+    #[serde(default = "default_counter_file")]
+    pub(crate) counter_file: String,
+    #[serde(default = "default_workspace_state_file")]
+    pub(crate) workspace_state_file: String,
+    #[serde(default = "default_volume_file")]
+    pub(crate) volume_file: String,
+    // Event name -> candidate sound files, so multiple increment sounds can
+    // be configured and one is picked at random via `Config::sound`.
+    #[serde(default = "default_sounds")]
+    pub(crate) sounds: HashMap<String, Vec<String>>,
+    #[serde(default = "default_special_mode_threshold")]
+    pub(crate) special_mode_threshold: u32,
+    #[serde(default = "default_backslash_trigger_count")]
+    pub(crate) backslash_trigger_count: u8,
+    #[serde(default)]
+    pub(crate) difficulty: DifficultyConfig,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            keyboards: default_keyboards(),
+            counter_file: default_counter_file(),
+            workspace_state_file: default_workspace_state_file(),
+            volume_file: default_volume_file(),
+            sounds: default_sounds(),
+            special_mode_threshold: default_special_mode_threshold(),
+            backslash_trigger_count: default_backslash_trigger_count(),
+            difficulty: DifficultyConfig::default(),
+        }
+    }
+}
+
+impl Config {
+    // Resolves `--config <path>` if given, otherwise
+    // `$XDG_CONFIG_HOME/sonic-waygame/config.toml`, falling back to the
+    // built-in defaults if the resolved file doesn't exist (a missing
+    // `--config` file does not fall through to the XDG path).
+    pub(crate) fn load(explicit_path: Option<&str>) -> Result<Self, Box<dyn Error>> {
+        let path = explicit_path.map(PathBuf::from).or_else(default_config_path);
+
+        match path {
+            Some(path) if path.exists() => {
+                let contents = fs::read_to_string(&path)?;
+                let mut config: Config = toml::from_str(&contents)?;
+                config.difficulty = config.difficulty.normalized();
+                println!("INFO: Loaded config from {}", path.display());
+                Ok(config)
+            }
+            Some(path) => {
+                println!(
+                    "INFO: No config file at {}; using built-in defaults.",
+                    path.display()
+                );
+                Ok(Config::default())
+            }
+            None => Ok(Config::default()),
+        }
+    }
+
+    // Picks one of the named event's candidate sound files at random, so a
+    // config with several increment sounds plays a different one each time.
+    pub(crate) fn sound(&self, event: &str) -> Option<String> {
+        let candidates = self.sounds.get(event)?;
+        if candidates.is_empty() {
+            return None;
+        }
+        let idx = rand::thread_rng().gen_range(0..candidates.len());
+        candidates.get(idx).cloned()
+    }
+}
+
+fn default_config_path() -> Option<PathBuf> {
+    let config_home = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(config_home.join("sonic-waygame").join("config.toml"))
+}
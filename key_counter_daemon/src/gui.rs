@@ -0,0 +1,101 @@
+/*
+ * src/gui.rs
+ *
+ * Optional FLTK dashboard window, enabled with `--gui`. Gives users who
+ * don't run Waybar a live view of the game state instead of the
+ * `/tmp/waybar_*.txt` files.
+ */
+
+use crate::AppState;
+use fltk::{
+    app,
+    enums::Color,
+    frame::Frame,
+    misc::Progress,
+    prelude::{GroupExt, ValuatorExt, WidgetExt},
+    window::Window,
+};
+use std::sync::{Arc, Mutex};
+
+// How often the dashboard re-reads the shared state. A periodic mutex
+// snapshot is simpler than threading the audio/status channels into the GUI
+// and is plenty responsive for a display that's just showing progress bars.
+const REFRESH_SECONDS: f64 = 0.2;
+
+// Blocks the calling thread until the window is closed. Intended to be run
+// from `main`'s thread so it doesn't steal the evdev listener or audio
+// threads away from their own work.
+pub(crate) fn run(state: Arc<Mutex<AppState>>) {
+    let app = app::App::default();
+    let mut win = Window::new(100, 100, 340, 260, "sonic-waygame dashboard");
+
+    let mut counter_label = Frame::new(10, 10, 320, 30, "Counter: 0");
+    let mut mode_label = Frame::new(10, 45, 320, 30, "Mode: Normal");
+
+    let mut keystroke_progress = Progress::new(10, 90, 320, 25, "Keystrokes");
+    keystroke_progress.set_minimum(0.0);
+    keystroke_progress.set_selection_color(Color::Blue);
+
+    let mut backslash_progress = Progress::new(10, 130, 320, 25, "Backslash count");
+    backslash_progress.set_minimum(0.0);
+    // Maximum is set per refresh from `config.backslash_trigger_count`
+    // instead of here, since that threshold is user-configurable.
+    backslash_progress.set_selection_color(Color::DarkYellow);
+
+    let mut super_sonic_banner = Frame::new(10, 175, 320, 60, "");
+    super_sonic_banner.set_label_size(22);
+    super_sonic_banner.set_label_color(Color::Red);
+
+    win.end();
+    win.show();
+
+    app::add_timeout3(REFRESH_SECONDS, move |handle| {
+        refresh(
+            &state,
+            &mut counter_label,
+            &mut mode_label,
+            &mut keystroke_progress,
+            &mut backslash_progress,
+            &mut super_sonic_banner,
+        );
+        app::repeat_timeout3(REFRESH_SECONDS, handle);
+    });
+
+    app.run().expect("FLTK event loop failed");
+}
+
+fn refresh(
+    state: &Arc<Mutex<AppState>>,
+    counter_label: &mut Frame,
+    mode_label: &mut Frame,
+    keystroke_progress: &mut Progress,
+    backslash_progress: &mut Progress,
+    super_sonic_banner: &mut Frame,
+) {
+    let state_guard = state.lock().unwrap();
+
+    counter_label.set_label(&format!("Counter: {}", state_guard.counter));
+    mode_label.set_label(&format!("Mode: {:?}", state_guard.game_mode));
+
+    keystroke_progress.set_maximum(state_guard.target_keystrokes as f64);
+    keystroke_progress.set_value(state_guard.keystroke_buffer as f64);
+    keystroke_progress.set_label(&format!(
+        "Keystrokes: {}/{}",
+        state_guard.keystroke_buffer, state_guard.target_keystrokes
+    ));
+
+    let backslash_trigger_count = state_guard.config.backslash_trigger_count;
+    backslash_progress.set_maximum(backslash_trigger_count as f64);
+    backslash_progress.set_value(state_guard.backslash_count as f64);
+    backslash_progress.set_label(&format!(
+        "Backslash count: {}/{}",
+        state_guard.backslash_count, backslash_trigger_count
+    ));
+
+    super_sonic_banner.set_label(if state_guard.is_decrementing {
+        "*** SUPER SONIC ***"
+    } else {
+        ""
+    });
+}
+
@@ -4,6 +4,11 @@
  * This is the main source code for the Rust application.
  */
 
+mod config;
+mod gui;
+
+use config::Config;
+use cpal::traits::{DeviceTrait, HostTrait};
 use evdev::{Device, InputEventKind, Key};
 use nix::fcntl::{flock, FlockArg};
 use rand::Rng;
@@ -14,68 +19,162 @@ use std::fs::{File, OpenOptions};
 use std::io::{BufReader, Read, Write};
 use std::os::unix::io::AsRawFd;
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{self, Sender};
+use std::sync::mpsc::{self, Receiver, Sender};
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 // --- Configuration ---
-const COUNTER_FILE: &str = "/tmp/waybar_counter.txt";
-const WORKSPACE_STATE_FILE: &str = "/tmp/waybar_status.txt";
+// File paths, sound files, keyboard hints, and difficulty thresholds all
+// live in `Config` now (see src/config.rs), loaded from TOML. What's left
+// here are the handful of knobs that aren't meaningfully per-install.
 const RESET_COUNTER_ON_START: bool = true;
-
-// --- IMPORTANT: Update these with the actual paths to your sound files ---
-const SPECIAL_MODE_SOUND_1: &str = "/home/jake/Music/Super-Sonic-Transform.mp3";
-const SPECIAL_MODE_SOUND_2: &str = "/home/jake/Music/Super-sonic-song.mp3";
-const INCREMENT_SOUND: &str = "/home/jake/Music/Sonic-Ring.mp3";
-
-// Hints to find the correct keyboards
-const KEYBOARD_HINTS: &[&str] = &["GMMK Pro Keyboard", "Translated"];
+const DEFAULT_VOLUME: f32 = 1.0;
+const VOLUME_STEP: f32 = 0.05;
 
 // --- Game Difficulty Modes ---
 #[derive(Clone, Copy, Debug)]
-enum GameMode {
+pub(crate) enum GameMode {
     Test,
     Normal,
     Hard,
 }
 
+// Rolls a new keystroke target for `mode`, using the inclusive range
+// configured for it in `Config::difficulty` rather than a hardcoded range.
+fn random_target(mode: GameMode, config: &Config) -> u32 {
+    let (low, high) = match mode {
+        GameMode::Test => config.difficulty.test_range,
+        GameMode::Normal => config.difficulty.normal_range,
+        GameMode::Hard => config.difficulty.hard_range,
+    };
+    rand::thread_rng().gen_range(low..=high)
+}
+
 // --- Shared Application State ---
 // This struct holds all the data that needs to be shared between threads.
-struct AppState {
-    counter: u32,
-    backslash_count: u8,
-    is_decrementing: bool,
-    keystroke_buffer: u32,
-    target_keystrokes: u32,
-    game_mode: GameMode,
+pub(crate) struct AppState {
+    pub(crate) counter: u32,
+    pub(crate) backslash_count: u8,
+    pub(crate) is_decrementing: bool,
+    pub(crate) keystroke_buffer: u32,
+    pub(crate) target_keystrokes: u32,
+    pub(crate) game_mode: GameMode,
+    pub(crate) volume: f32,
+    pub(crate) config: Arc<Config>,
+    // Bumped every time special mode triggers, and stamped onto the
+    // `PlayAndLoop` command that kicks it off. Lets `audio_status_listener`
+    // arm the decrementer for *this* activation specifically, instead of
+    // inferring "this `TrackStarted` belongs to special mode" from the
+    // global `is_decrementing` flag, which only happened to be correct
+    // because ordinary increments always intervene between triggers.
+    pub(crate) special_mode_generation: u64,
 }
 
 // --- Commands for the Audio Thread ---
 enum AudioCommand {
     Play(Vec<String>),
-    PlayAndLoop { intro: String, looping: String },
+    PlayAndLoop {
+        intro: String,
+        looping: String,
+        generation: u64,
+    },
     Stop,
+    Reload,
+    SetVolume(f32),
+}
+
+// How long the super-sonic loop takes to fade in once it starts playing.
+const VOLUME_FADE_IN: Duration = Duration::from_millis(500);
+const VOLUME_FADE_STEPS: u32 = 20;
+
+// How many seconds before the decrementer hits zero the transform music
+// starts fading out, so it recedes instead of cutting abruptly.
+const DECREMENT_FADE_OUT_SECS: u32 = 2;
+// How many volume steps to spread that fade-out over, mirroring the
+// resolution of the `PlayAndLoop` fade-in rather than relying on the
+// decrementer's coarse 1 Hz tick.
+const DECREMENT_FADE_OUT_STEPS: u32 = 40;
+
+// How long to wait between attempts to (re)open the output device
+// once it's gone missing, so a disconnected DAC doesn't spin the audio
+// thread hot.
+const AUDIO_RETRY_BACKOFF: Duration = Duration::from_secs(2);
+
+// --- Status Updates From the Audio Thread ---
+// These flow back the other way, so callers of `audio_tx.send(...)` aren't
+// just firing commands into a void: they can find out whether a sound
+// actually started, finished, or blew up on decode.
+#[derive(Debug)]
+enum AudioStatusMessage {
+    // `Some(generation)` when this track started as the intro of a
+    // `PlayAndLoop`, carrying that command's generation; `None` for a plain
+    // `Play` (e.g. the increment sound), which doesn't arm anything.
+    TrackStarted(Option<u64>),
+    TrackFinished,
+    LoopStarted,
+    DecodeError(String),
+    StreamLost,
 }
 
 fn main() -> Result<(), Box<dyn Error>> {
     // --- Parse Command-Line Arguments ---
+    let args: Vec<String> = env::args().collect();
     let mut game_mode = GameMode::Normal; // Default mode
-    if let Some(arg) = env::args().nth(1) {
-        match arg.as_str() {
+    let mut output_device_hint: Option<String> = None;
+    let mut gui_enabled = false;
+    let mut config_path: Option<String> = None;
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
             "--test" => game_mode = GameMode::Test,
             "--normal" => game_mode = GameMode::Normal,
             "--hard" => game_mode = GameMode::Hard,
-            _ => println!("WARNING: Unknown argument '{}'. Defaulting to normal mode.", arg),
+            "--gui" => gui_enabled = true,
+            "--output-device" => {
+                i += 1;
+                match args.get(i) {
+                    Some(value) => output_device_hint = Some(value.clone()),
+                    None => eprintln!("WARNING: --output-device requires a value (a name substring, or 'list')."),
+                }
+            }
+            "--config" => {
+                i += 1;
+                match args.get(i) {
+                    Some(value) => config_path = Some(value.clone()),
+                    None => eprintln!("WARNING: --config requires a path to a TOML file."),
+                }
+            }
+            other => println!("WARNING: Unknown argument '{}'. Ignoring it.", other),
         }
+        i += 1;
     }
+
+    // `--output-device list` just enumerates what cpal can see and exits,
+    // mirroring how keyboards are matched by name substring.
+    if output_device_hint.as_deref() == Some("list") {
+        list_output_devices();
+        return Ok(());
+    }
+
+    // Uses `$XDG_CONFIG_HOME/sonic-waygame/config.toml` if `--config` wasn't
+    // given, and built-in defaults if the chosen file is missing.
+    let config = Arc::new(Config::load(config_path.as_deref())?);
+
     println!("INFO: Starting in {:?} mode.", game_mode);
 
     // Set the initial random target based on the selected game mode.
-    let initial_target = match game_mode {
-        GameMode::Test => 1,
-        GameMode::Normal => rand::thread_rng().gen_range(1..=100),
-        GameMode::Hard => rand::thread_rng().gen_range(1..=1000),
+    let initial_target = random_target(game_mode, &config);
+
+    // The base volume is persisted independently of the counter, so it
+    // survives the `RESET_COUNTER_ON_START` reinitialization below.
+    let initial_volume = if Path::new(&config.volume_file).exists() {
+        read_from_file(&config.volume_file)?
+            .trim()
+            .parse()
+            .unwrap_or(DEFAULT_VOLUME)
+    } else {
+        DEFAULT_VOLUME
     };
 
     // Initialize the shared state
@@ -86,33 +185,55 @@ fn main() -> Result<(), Box<dyn Error>> {
         keystroke_buffer: 0,
         target_keystrokes: initial_target,
         game_mode,
+        volume: initial_volume,
+        config: Arc::clone(&config),
+        special_mode_generation: 0,
     }));
 
-    // Create a channel for sending commands to the audio thread
+    // Create a channel for sending commands to the audio thread, and a second
+    // channel flowing the other way so the audio thread can report back what
+    // actually happened (started, finished, failed to decode, ...).
     let (audio_tx, audio_rx) = mpsc::channel();
+    let (status_tx, status_rx) = mpsc::channel();
 
     // Spawn the dedicated audio thread
     thread::spawn(move || {
-        audio_thread_loop(audio_rx);
+        audio_thread_loop(audio_rx, status_tx, output_device_hint);
     });
+    audio_tx.send(AudioCommand::SetVolume(initial_volume))?;
+
+    // Spawn a thread to react to audio status updates: log decode failures
+    // instead of letting them vanish, and only kick off the decrementer once
+    // the super-sonic intro has actually started playing.
+    {
+        let state_clone = Arc::clone(&state);
+        let audio_tx_clone = audio_tx.clone();
+        thread::spawn(move || {
+            audio_status_listener(status_rx, state_clone, audio_tx_clone);
+        });
+    }
 
     // Initialize or reset the counter and workspace state files
-    if RESET_COUNTER_ON_START || !Path::new(COUNTER_FILE).exists() {
-        write_to_file(COUNTER_FILE, "0")?;
-        write_to_file(WORKSPACE_STATE_FILE, "flashing")?;
+    if RESET_COUNTER_ON_START || !Path::new(&config.counter_file).exists() {
+        write_to_file(&config.counter_file, "0")?;
+        write_to_file(&config.workspace_state_file, "flashing")?;
     } else {
         // On start, load the counter from the file into our state
         let mut state_guard = state.lock().unwrap();
-        state_guard.counter = read_from_file(COUNTER_FILE)?.parse().unwrap_or(0);
+        state_guard.counter = read_from_file(&config.counter_file)?.parse().unwrap_or(0);
     }
 
     // --- Find and spawn listeners for all specified keyboards ---
     let devices = evdev::enumerate().collect::<Vec<_>>();
-    for hint in KEYBOARD_HINTS {
+    for hint in &config.keyboards {
         if let Some(path) = find_device_path(&devices, hint) {
             println!("INFO: Found keyboard matching '{}' at {}", hint, path.display());
             let state_clone = Arc::clone(&state);
             let audio_tx_clone = audio_tx.clone();
+            // Own the hint instead of borrowing from `config`: the `move`
+            // closure runs on its own thread and can outlive this function's
+            // stack frame, so it can't hold a reference into `config.keyboards`.
+            let hint = hint.clone();
             thread::spawn(move || {
                 if let Err(e) = event_listener(path, state_clone, audio_tx_clone) {
                     eprintln!("ERROR: Listener thread for {} failed: {}", hint, e);
@@ -123,6 +244,13 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
     }
 
+    // With `--gui`, hand the main thread over to the FLTK dashboard instead
+    // of just parking it; the window blocks until the user closes it.
+    if gui_enabled {
+        gui::run(Arc::clone(&state));
+        return Ok(());
+    }
+
     // Keep the main thread alive indefinitely
     loop {
         thread::park();
@@ -164,34 +292,75 @@ fn process_key_event(
         return Ok(());
     }
 
+    // Reload the sound manager on demand, e.g. after plugging the DAC back in.
+    if key_code == Key::KEY_F5.code() {
+        println!("ACTION: Reload key pressed. Rebuilding sound manager.");
+        audio_tx.send(AudioCommand::Reload)?;
+        return Ok(());
+    }
+
+    // Volume up/down work even while the decrementer is running, so the
+    // super-sonic swell can still be adjusted live.
+    if key_code == Key::KEY_VOLUMEUP.code() || key_code == Key::KEY_VOLUMEDOWN.code() {
+        let delta = if key_code == Key::KEY_VOLUMEUP.code() {
+            VOLUME_STEP
+        } else {
+            -VOLUME_STEP
+        };
+        state_guard.volume = (state_guard.volume + delta).clamp(0.0, 1.0);
+        println!("ACTION: Volume set to {:.2}", state_guard.volume);
+        audio_tx.send(AudioCommand::SetVolume(state_guard.volume))?;
+        write_to_file(&state_guard.config.volume_file, &state_guard.volume.to_string())?;
+        return Ok(());
+    }
+
     // If the decrementer is running, ignore all other key presses.
     if state_guard.is_decrementing {
         return Ok(());
     }
 
     // Key code for '\' is 43 (KEY_BACKSLASH)
-    if key_code == Key::KEY_BACKSLASH.code() && state_guard.counter >= 50 {
+    if key_code == Key::KEY_BACKSLASH.code()
+        && state_guard.counter >= state_guard.config.special_mode_threshold
+    {
         state_guard.backslash_count += 1;
         println!("INFO: Backslash pressed. Count: {}", state_guard.backslash_count);
 
-        if state_guard.backslash_count >= 3 {
+        if state_guard.backslash_count >= state_guard.config.backslash_trigger_count {
             println!("ACTION: Special mode triggered!");
             state_guard.is_decrementing = true;
             state_guard.backslash_count = 0;
-            write_to_file(WORKSPACE_STATE_FILE, "super-charge-flash")?;
+            state_guard.special_mode_generation += 1;
+            let generation = state_guard.special_mode_generation;
+            write_to_file(&state_guard.config.workspace_state_file, "super-charge-flash")?;
 
             // Send a command to play the intro and then loop the main song.
-            audio_tx.send(AudioCommand::PlayAndLoop {
-                intro: SPECIAL_MODE_SOUND_1.to_string(),
-                looping: SPECIAL_MODE_SOUND_2.to_string(),
-            })?;
-
-            // Spawn a new thread for the decrementer, passing it the audio sender
-            let state_clone = Arc::clone(&state);
-            let audio_tx_clone = audio_tx.clone();
-            thread::spawn(move || {
-                decrementer_loop(state_clone, audio_tx_clone);
-            });
+            // The decrementer isn't spawned here anymore: `audio_status_listener`
+            // starts it once it hears back that the intro has actually begun,
+            // correlated to this specific activation via `generation`.
+            let intro = state_guard.config.sound("special_intro");
+            let looping = state_guard.config.sound("special_loop");
+            match (intro, looping) {
+                (Some(intro), Some(looping)) => {
+                    audio_tx.send(AudioCommand::PlayAndLoop {
+                        intro,
+                        looping,
+                        generation,
+                    })?;
+                }
+                _ => {
+                    eprintln!(
+                        "WARNING: No 'special_intro'/'special_loop' sound configured; skipping special mode audio."
+                    );
+                    // No intro will report starting, so start the decrementer
+                    // here directly.
+                    let state_clone = Arc::clone(&state);
+                    let audio_tx_clone = audio_tx.clone();
+                    thread::spawn(move || {
+                        decrementer_loop(state_clone, audio_tx_clone);
+                    });
+                }
+            }
         }
     } else {
         // On any other key, reset the backslash count and handle keystroke buffering.
@@ -205,11 +374,8 @@ fn process_key_event(
             // Increment the main counter
             state_guard.counter += 1;
             // Set a new random target for the next increment based on the game mode.
-            state_guard.target_keystrokes = match state_guard.game_mode {
-                GameMode::Test => 1,
-                GameMode::Normal => rand::thread_rng().gen_range(1..=100),
-                GameMode::Hard => rand::thread_rng().gen_range(1..=1000),
-            };
+            state_guard.target_keystrokes =
+                random_target(state_guard.game_mode, &state_guard.config);
 
             println!(
                 "ACTION: Counter incremented to {}. Next increment in {} keystrokes.",
@@ -217,10 +383,13 @@ fn process_key_event(
             );
 
             // Play the increment sound
-            audio_tx.send(AudioCommand::Play(vec![INCREMENT_SOUND.to_string()]))?;
+            match state_guard.config.sound("increment") {
+                Some(sound) => audio_tx.send(AudioCommand::Play(vec![sound]))?,
+                None => eprintln!("WARNING: No 'increment' sound configured; skipping playback."),
+            }
 
             // Update the counter file for Waybar to read.
-            write_to_file(COUNTER_FILE, &state_guard.counter.to_string())?;
+            write_to_file(&state_guard.config.counter_file, &state_guard.counter.to_string())?;
         }
     }
 
@@ -229,95 +398,426 @@ fn process_key_event(
 
 // --- Decrementer Thread ---
 fn decrementer_loop(state: Arc<Mutex<AppState>>, audio_tx: Sender<AudioCommand>) {
+    // Holds the fade-out thread so we can join it before sending `Stop`/the
+    // restoring `SetVolume` below — otherwise both threads race to send the
+    // last word on `audio_tx`, and if the fade-out's near-zero `SetVolume`
+    // lands after the restore, the sound manager is left stuck near-silent
+    // until the user manually touches volume-up/down.
+    let mut fade_out_handle: Option<thread::JoinHandle<()>> = None;
+
     loop {
         thread::sleep(Duration::from_secs(1));
         let mut state_guard = state.lock().unwrap();
 
         if state_guard.counter > 0 {
             state_guard.counter -= 1;
-            if let Err(e) = write_to_file(COUNTER_FILE, &state_guard.counter.to_string()) {
+            if let Err(e) = write_to_file(&state_guard.config.counter_file, &state_guard.counter.to_string()) {
                 eprintln!("ERROR: Failed to write to counter file: {}", e);
             }
+
+            // Kick off a finely-stepped fade-out as we enter the last
+            // `DECREMENT_FADE_OUT_SECS` seconds, instead of only adjusting
+            // volume on this loop's own 1 Hz tick (too coarse to read as a
+            // swell). Runs on its own thread so it isn't gated by the
+            // decrementer's one-second sleep.
+            if state_guard.counter == DECREMENT_FADE_OUT_SECS {
+                let audio_tx_clone = audio_tx.clone();
+                let base_volume = state_guard.volume;
+                fade_out_handle = Some(thread::spawn(move || fade_out(audio_tx_clone, base_volume)));
+            }
         } else {
             println!("INFO: Decrementer finished. Resetting state and stopping music.");
             state_guard.is_decrementing = false;
-            if let Err(e) = write_to_file(WORKSPACE_STATE_FILE, "flashing") {
+            if let Err(e) = write_to_file(&state_guard.config.workspace_state_file, "flashing") {
                 eprintln!("ERROR: Failed to write to workspace state file: {}", e);
             }
+            // Make sure the fade-out has sent its last `SetVolume` before we
+            // send our own, so the two senders can't race on ordering.
+            if let Some(handle) = fade_out_handle.take() {
+                let _ = handle.join();
+            }
             // Send the stop command to the audio thread
             if let Err(e) = audio_tx.send(AudioCommand::Stop) {
                 eprintln!("ERROR: Failed to send Stop command from decrementer: {}", e);
             }
+            // Restore the base volume so the next PlayAndLoop fades in from a
+            // clean target instead of the faded-down remnant.
+            if let Err(e) = audio_tx.send(AudioCommand::SetVolume(state_guard.volume)) {
+                eprintln!("ERROR: Failed to restore base volume: {}", e);
+            }
             break;
         }
     }
 }
 
-// --- Dedicated Audio Thread ---
-fn audio_thread_loop(rx: mpsc::Receiver<AudioCommand>) {
-    // Get an output stream handle to the default physical sound device
-    let (_stream, stream_handle) = match OutputStream::try_default() {
-        Ok(stream) => stream,
-        Err(e) => {
-            eprintln!("ERROR: Could not get audio output stream: {}", e);
-            return;
+// Ramps the output volume from `base_volume` down to silence over
+// `DECREMENT_FADE_OUT_SECS`, in `DECREMENT_FADE_OUT_STEPS` steps — the same
+// stepped-sleep shape as the `PlayAndLoop` fade-in, just running down
+// instead of up. Stops early if the audio thread has gone away.
+fn fade_out(audio_tx: Sender<AudioCommand>, base_volume: f32) {
+    let step_duration = Duration::from_secs(DECREMENT_FADE_OUT_SECS as u64) / DECREMENT_FADE_OUT_STEPS;
+    for step in 1..=DECREMENT_FADE_OUT_STEPS {
+        thread::sleep(step_duration);
+        let remaining = 1.0 - (step as f32 / DECREMENT_FADE_OUT_STEPS as f32);
+        if audio_tx
+            .send(AudioCommand::SetVolume(base_volume * remaining))
+            .is_err()
+        {
+            break;
         }
-    };
+    }
+}
 
-    // Create a sink to play sounds
-    let sink = match Sink::try_new(&stream_handle) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("ERROR: Could not create audio sink: {}", e);
-            return;
+// --- Audio Status Listener Thread ---
+// Reacts to `AudioStatusMessage`s coming back from the audio thread so the
+// rest of the app behaves like a peer of the audio thread instead of
+// fire-and-forgetting commands at it.
+fn audio_status_listener(
+    rx: Receiver<AudioStatusMessage>,
+    state: Arc<Mutex<AppState>>,
+    audio_tx: Sender<AudioCommand>,
+) {
+    // The generation the decrementer has already been armed for, so a
+    // repeated `TrackStarted` carrying the same generation doesn't spawn a
+    // duplicate decrementer. Compared
+    // against `AppState::special_mode_generation` rather than inferred from
+    // `is_decrementing`, so arming is tied to the specific activation that
+    // produced this message instead of "decrementing happens to be on".
+    let mut armed_generation: Option<u64> = None;
+
+    for status in rx {
+        match status {
+            AudioStatusMessage::TrackStarted(generation) => {
+                println!("AUDIO: Track started.");
+                if let Some(generation) = generation {
+                    let (is_decrementing, current_generation) = {
+                        let guard = state.lock().unwrap();
+                        (guard.is_decrementing, guard.special_mode_generation)
+                    };
+                    if is_decrementing
+                        && current_generation == generation
+                        && armed_generation != Some(generation)
+                    {
+                        armed_generation = Some(generation);
+                        let state_clone = Arc::clone(&state);
+                        let audio_tx_clone = audio_tx.clone();
+                        thread::spawn(move || {
+                            decrementer_loop(state_clone, audio_tx_clone);
+                        });
+                    }
+                }
+            }
+            AudioStatusMessage::LoopStarted => {
+                println!("AUDIO: Loop started.");
+            }
+            AudioStatusMessage::TrackFinished => {
+                println!("AUDIO: Track finished.");
+            }
+            AudioStatusMessage::DecodeError(path) => {
+                eprintln!("ERROR: Failed to decode audio file '{}'.", path);
+            }
+            AudioStatusMessage::StreamLost => {
+                eprintln!("ERROR: Audio output stream was lost.");
+            }
         }
-    };
+    }
+}
 
-    // This loop waits for commands from the main application.
-    for command in rx {
-        match command {
-            AudioCommand::Play(sound_paths) => {
-                println!("AUDIO: Received Play command.");
-                sink.stop();
-
-                for path_str in sound_paths.iter() {
-                    if let Ok(file) = File::open(path_str) {
-                        if let Ok(source) = Decoder::new(BufReader::new(file)) {
-                            sink.append(source);
-                        }
-                    }
+// --- Gapless Looping Buffer ---
+// A `Source` over an in-memory, infinitely-cycling sample buffer that keeps
+// the channel layout and sample rate of the file it was decoded from, unlike
+// `rodio::source::from_iter` which falls back to rodio's defaults (44.1 kHz
+// stereo) and lets interleaved channels desync.
+struct LoopedBuffer {
+    samples: Arc<Vec<i16>>,
+    pos: usize,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl LoopedBuffer {
+    fn new(samples: Vec<i16>, channels: u16, sample_rate: u32) -> Self {
+        Self {
+            samples: Arc::new(samples),
+            pos: 0,
+            channels,
+            sample_rate,
+        }
+    }
+}
+
+impl Iterator for LoopedBuffer {
+    type Item = i16;
+
+    fn next(&mut self) -> Option<i16> {
+        // An empty buffer (e.g. a decode that produced no samples) means
+        // silence rather than a modulo-by-zero panic.
+        if self.samples.is_empty() {
+            return None;
+        }
+        let sample = self.samples[self.pos];
+        self.pos = (self.pos + 1) % self.samples.len();
+        Some(sample)
+    }
+}
+
+impl Source for LoopedBuffer {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// --- Self-Healing Output Device Handle ---
+// Owns the `(OutputStream, Sink)` pair as an `Option` so losing the device
+// (USB DAC unplugged, etc.) doesn't kill the audio thread: the next command
+// just lazily reopens the output device (the `--output-device` match, or the
+// default) instead of finding a dead receiver.
+struct SoundManager {
+    stream_and_sink: Option<(OutputStream, Sink)>,
+    last_retry: Option<Instant>,
+    output_device_hint: Option<String>,
+    // The last volume set via `AudioCommand::SetVolume`, reapplied whenever
+    // the sink is (re)built so a device reconnect doesn't reset playback
+    // back to full volume.
+    volume: f32,
+}
+
+impl SoundManager {
+    fn new(output_device_hint: Option<String>) -> Self {
+        Self {
+            stream_and_sink: None,
+            last_retry: None,
+            output_device_hint,
+            volume: DEFAULT_VOLUME,
+        }
+    }
+
+    fn try_build(&self) -> Result<(OutputStream, Sink), Box<dyn Error>> {
+        let (stream, stream_handle) = match &self.output_device_hint {
+            Some(hint) => match find_output_device_by_hint(hint) {
+                Some(device) => OutputStream::try_from_device(&device)?,
+                None => {
+                    eprintln!(
+                        "WARNING: No output device matching '{}' found; falling back to default.",
+                        hint
+                    );
+                    OutputStream::try_default()?
                 }
-                sink.play();
+            },
+            None => OutputStream::try_default()?,
+        };
+        let sink = Sink::try_new(&stream_handle)?;
+        Ok((stream, sink))
+    }
+
+    // Makes sure the output stream/sink are present, (re)building them if
+    // necessary. Respects `AUDIO_RETRY_BACKOFF` so a missing device doesn't
+    // spin the audio thread hot retrying on every command.
+    fn ensure_ready(&mut self, status_tx: &Sender<AudioStatusMessage>) -> bool {
+        if self.stream_and_sink.is_some() {
+            return true;
+        }
+        if let Some(last) = self.last_retry {
+            if last.elapsed() < AUDIO_RETRY_BACKOFF {
+                return false;
             }
-            AudioCommand::PlayAndLoop { intro, looping } => {
-                println!("AUDIO: Received PlayAndLoop command.");
-                sink.stop();
-
-                // Append the intro sound (plays once)
-                if let Ok(file) = File::open(&intro) {
-                    if let Ok(source) = Decoder::new(BufReader::new(file)) {
-                        sink.append(source);
-                    }
+        }
+        self.last_retry = Some(Instant::now());
+        match self.try_build() {
+            Ok((stream, sink)) => {
+                println!("AUDIO: Output device (re)connected.");
+                sink.set_volume(self.volume);
+                self.stream_and_sink = Some((stream, sink));
+                true
+            }
+            Err(e) => {
+                eprintln!("ERROR: Could not (re)connect to audio output: {}", e);
+                let _ = status_tx.send(AudioStatusMessage::StreamLost);
+                false
+            }
+        }
+    }
+
+    fn sink(&self) -> Option<&Sink> {
+        self.stream_and_sink.as_ref().map(|(_, sink)| sink)
+    }
+
+    // Tears the handles down so the next command forces a fresh
+    // `OutputStream`/`Sink` — used both after a playback failure and via the
+    // "reload sound manager" hotkey.
+    fn reload(&mut self) {
+        self.stream_and_sink = None;
+        self.last_retry = None;
+    }
+}
+
+// --- Dedicated Audio Thread ---
+fn audio_thread_loop(
+    rx: mpsc::Receiver<AudioCommand>,
+    status_tx: Sender<AudioStatusMessage>,
+    output_device_hint: Option<String>,
+) {
+    let mut manager = SoundManager::new(output_device_hint);
+    manager.ensure_ready(&status_tx);
+
+    // Tracks whether the sink was playing something last time we looked, so
+    // we can notice the empty-transition and emit `TrackFinished`.
+    let mut was_playing = false;
+
+    loop {
+        // Poll with a short timeout instead of blocking forever on `recv`, so
+        // we can also notice when the sink naturally empties out.
+        match rx.recv_timeout(Duration::from_millis(100)) {
+            Ok(command) => {
+                if let AudioCommand::Reload = command {
+                    println!("AUDIO: Received Reload command.");
+                    manager.reload();
+                    manager.ensure_ready(&status_tx);
+                    was_playing = false;
+                    continue;
                 }
 
-                // For the looping sound, decode it into an in-memory buffer
-                // to allow for seamless, gapless looping.
-                if let Ok(file) = File::open(&looping) {
-                    if let Ok(source) = Decoder::new(BufReader::new(file)) {
-                        // Collect all the decoded audio samples into a vector
-                        let samples: Vec<i16> = source.convert_samples().collect();
-                        // Create a new source that infinitely cycles through the in-memory samples
-                        let looping_source =
-                            rodio::source::from_iter(samples.into_iter().cycle());
-                        sink.append(looping_source);
+                // Track the target volume on the manager itself (so it
+                // survives sink rebuilds) before doing anything that might
+                // need the device ready.
+                if let AudioCommand::SetVolume(volume) = &command {
+                    manager.volume = *volume;
+                }
+
+                if !manager.ensure_ready(&status_tx) {
+                    eprintln!("WARNING: Dropping audio command; output device unavailable.");
+                    continue;
+                }
+                let sink = manager.sink().expect("ensure_ready just confirmed this");
+                let target_volume = manager.volume;
+
+                // `Sink::append`/`play` can panic if the underlying cpal
+                // stream thread has died (e.g. the device was unplugged
+                // mid-playback); catch that so we can tear down and retry
+                // instead of taking the whole audio thread down with it.
+                let played = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+                    match &command {
+                        AudioCommand::Play(sound_paths) => {
+                            println!("AUDIO: Received Play command.");
+                            sink.stop();
+
+                            for path_str in sound_paths.iter() {
+                                if let Ok(file) = File::open(path_str) {
+                                    if let Ok(source) = Decoder::new(BufReader::new(file)) {
+                                        sink.append(source);
+                                    } else {
+                                        let _ = status_tx.send(AudioStatusMessage::DecodeError(
+                                            path_str.clone(),
+                                        ));
+                                    }
+                                } else {
+                                    let _ = status_tx
+                                        .send(AudioStatusMessage::DecodeError(path_str.clone()));
+                                }
+                            }
+                            sink.play();
+                            let _ = status_tx.send(AudioStatusMessage::TrackStarted(None));
+                        }
+                        AudioCommand::PlayAndLoop {
+                            intro,
+                            looping,
+                            generation,
+                        } => {
+                            println!("AUDIO: Received PlayAndLoop command.");
+                            sink.stop();
+
+                            // Append the intro sound (plays once)
+                            if let Ok(file) = File::open(intro) {
+                                if let Ok(source) = Decoder::new(BufReader::new(file)) {
+                                    sink.append(source);
+                                } else {
+                                    let _ = status_tx
+                                        .send(AudioStatusMessage::DecodeError(intro.clone()));
+                                }
+                            } else {
+                                let _ =
+                                    status_tx.send(AudioStatusMessage::DecodeError(intro.clone()));
+                            }
+
+                            // For the looping sound, decode it into an in-memory buffer
+                            // to allow for seamless, gapless looping.
+                            if let Ok(file) = File::open(looping) {
+                                if let Ok(source) = Decoder::new(BufReader::new(file)) {
+                                    // Capture the source's own channel/rate before
+                                    // collecting, so the looped playback doesn't
+                                    // default to rodio's assumptions and desync.
+                                    let channels = source.channels();
+                                    let sample_rate = source.sample_rate();
+                                    let samples: Vec<i16> = source.convert_samples().collect();
+                                    let looping_source =
+                                        LoopedBuffer::new(samples, channels, sample_rate);
+                                    sink.append(looping_source);
+                                    let _ = status_tx.send(AudioStatusMessage::LoopStarted);
+                                } else {
+                                    let _ = status_tx
+                                        .send(AudioStatusMessage::DecodeError(looping.clone()));
+                                }
+                            } else {
+                                let _ = status_tx
+                                    .send(AudioStatusMessage::DecodeError(looping.clone()));
+                            }
+                            // Fade the loop in over `VOLUME_FADE_IN` instead of
+                            // snapping straight to the target volume, so the
+                            // transform music swells in rather than cutting on.
+                            sink.set_volume(0.0);
+                            sink.play();
+                            let _ = status_tx.send(AudioStatusMessage::TrackStarted(Some(*generation)));
+                            for step in 1..=VOLUME_FADE_STEPS {
+                                thread::sleep(VOLUME_FADE_IN / VOLUME_FADE_STEPS);
+                                sink.set_volume(target_volume * (step as f32 / VOLUME_FADE_STEPS as f32));
+                            }
+                        }
+                        AudioCommand::Stop => {
+                            println!("AUDIO: Received Stop command.");
+                            sink.stop();
+                        }
+                        AudioCommand::SetVolume(volume) => {
+                            println!("AUDIO: Received SetVolume command ({:.2}).", volume);
+                            sink.set_volume(*volume);
+                        }
+                        AudioCommand::Reload => unreachable!("handled above"),
                     }
+                }))
+                .is_ok();
+
+                if !played {
+                    eprintln!("ERROR: Playback panicked; rebuilding sound manager.");
+                    manager.reload();
+                    let _ = status_tx.send(AudioStatusMessage::StreamLost);
+                    was_playing = false;
+                    continue;
                 }
-                sink.play();
+
+                was_playing = manager.sink().map_or(false, |s| !s.empty());
             }
-            AudioCommand::Stop => {
-                println!("AUDIO: Received Stop command.");
-                sink.stop();
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                if !manager.ensure_ready(&status_tx) {
+                    continue;
+                }
+                let playing_now = manager.sink().map_or(false, |s| !s.empty());
+                if was_playing && !playing_now {
+                    let _ = status_tx.send(AudioStatusMessage::TrackFinished);
+                }
+                was_playing = playing_now;
             }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
         }
     }
 }
@@ -332,6 +832,32 @@ fn find_device_path(devices: &[(PathBuf, Device)], hint: &str) -> Option<PathBuf
         .map(|(path, _device)| path.clone())
 }
 
+// Finds the first cpal output device whose name contains the hint, mirroring
+// how keyboards are matched by name substring in `find_device_path`.
+fn find_output_device_by_hint(hint: &str) -> Option<cpal::Device> {
+    let host = cpal::default_host();
+    host.output_devices()
+        .ok()?
+        .find(|device| device.name().map_or(false, |name| name.contains(hint)))
+}
+
+// Prints every output device cpal can see, for `--output-device list`.
+fn list_output_devices() {
+    let host = cpal::default_host();
+    println!("INFO: Available output devices:");
+    match host.output_devices() {
+        Ok(devices) => {
+            for device in devices {
+                match device.name() {
+                    Ok(name) => println!("  - {}", name),
+                    Err(e) => eprintln!("  - <unnamed device: {}>", e),
+                }
+            }
+        }
+        Err(e) => eprintln!("ERROR: Could not enumerate output devices: {}", e),
+    }
+}
+
 // Helper to read from a file, with a file lock for safety.
 fn read_from_file(path: &str) -> Result<String, Box<dyn Error>> {
     let mut file = OpenOptions::new().read(true).open(path)?;